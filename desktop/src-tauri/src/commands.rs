@@ -1,7 +1,103 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use thiserror::Error;
 
 const API_BASE: &str = "http://localhost:3000/api";
 
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "kind")]
+pub enum CommandError {
+    #[error("backend returned {status}: {body}")]
+    Http { status: u16, body: String },
+    #[error("network error: {0}")]
+    Network(String),
+    #[error("failed to decode response: {0}")]
+    Decode(String),
+    #[error("backend is not configured")]
+    NotConfigured,
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+}
+
+impl From<reqwest::Error> for CommandError {
+    fn from(error: reqwest::Error) -> Self {
+        CommandError::Network(error.to_string())
+    }
+}
+
+fn status_to_command_error(status: reqwest::StatusCode, body: String) -> CommandError {
+    CommandError::Http { status: status.as_u16(), body }
+}
+
+async fn decode<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T, CommandError> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(status_to_command_error(status, body));
+    }
+    response
+        .json::<T>()
+        .await
+        .map_err(|e| CommandError::Decode(e.to_string()))
+}
+
+pub struct AppState {
+    pub base_url: String,
+    pub token: Option<String>,
+    pub client: reqwest::Client,
+    snapshot_stream_cancel: Option<Arc<AtomicBool>>,
+    agent_log_cancels: HashMap<String, Arc<AtomicBool>>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            base_url: API_BASE.to_string(),
+            token: None,
+            client: reqwest::Client::new(),
+            snapshot_stream_cancel: None,
+            agent_log_cancels: HashMap::new(),
+        }
+    }
+}
+
+impl AppState {
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        build_request(&self.client, &self.base_url, &self.token, method, path)
+    }
+
+    /// Guards commands that would otherwise send requests to an empty base
+    /// URL, e.g. after `configure("", ...)`.
+    fn ensure_configured(&self) -> Result<(), CommandError> {
+        if self.base_url.is_empty() {
+            Err(CommandError::NotConfigured)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Used by background tasks that can't hold the `AppState` mutex across an `.await`.
+fn build_request(
+    client: &reqwest::Client,
+    base_url: &str,
+    token: &Option<String>,
+    method: reqwest::Method,
+    path: &str,
+) -> reqwest::RequestBuilder {
+    let builder = client.request(method, format!("{base_url}{path}"));
+    match token {
+        Some(token) => builder.bearer_auth(token),
+        None => builder,
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HealthResponse {
     pub status: String,
@@ -52,62 +148,351 @@ pub struct SubmitTaskResponse {
     pub status: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum TaskState {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskStatus {
+    #[serde(rename = "taskId")]
+    pub task_id: String,
+    pub name: String,
+    pub state: TaskState,
+    #[serde(rename = "assignedAgent")]
+    pub assigned_agent: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentLogLine {
+    #[serde(rename = "agentId")]
+    pub agent_id: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentLogError {
+    #[serde(rename = "agentId")]
+    pub agent_id: String,
+    #[serde(flatten)]
+    pub error: CommandError,
+}
+
+#[tracing::instrument(skip(state, token))]
 #[tauri::command]
-pub async fn get_health() -> Result<HealthResponse, String> {
-    let client = reqwest::Client::new();
-    client
-        .get(format!("{API_BASE}/health"))
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json::<HealthResponse>()
-        .await
-        .map_err(|e| e.to_string())
+pub fn configure(state: State<'_, Mutex<AppState>>, base_url: String, token: Option<String>) {
+    let mut state = state.lock().unwrap();
+    state.base_url = base_url;
+    state.token = token;
 }
 
+#[tracing::instrument(skip(state))]
 #[tauri::command]
-pub async fn get_snapshot() -> Result<DashboardSnapshot, String> {
-    let client = reqwest::Client::new();
-    client
-        .get(format!("{API_BASE}/snapshot"))
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json::<DashboardSnapshot>()
-        .await
-        .map_err(|e| e.to_string())
+pub fn is_configured(state: State<'_, Mutex<AppState>>) -> bool {
+    state.lock().unwrap().ensure_configured().is_ok()
+}
+
+#[tracing::instrument(skip(state))]
+#[tauri::command]
+pub async fn get_health(state: State<'_, Mutex<AppState>>) -> Result<HealthResponse, CommandError> {
+    let request = {
+        let state = state.lock().unwrap();
+        state.ensure_configured()?;
+        state.request(reqwest::Method::GET, "/health")
+    };
+    decode(request.send().await?).await
 }
 
+#[tracing::instrument(skip(state))]
 #[tauri::command]
-pub async fn get_agents() -> Result<Vec<AgentSnapshot>, String> {
+pub async fn get_snapshot(state: State<'_, Mutex<AppState>>) -> Result<DashboardSnapshot, CommandError> {
+    let request = {
+        let state = state.lock().unwrap();
+        state.ensure_configured()?;
+        state.request(reqwest::Method::GET, "/snapshot")
+    };
+    decode(request.send().await?).await
+}
+
+#[tracing::instrument(skip(state))]
+#[tauri::command]
+pub async fn get_agents(state: State<'_, Mutex<AppState>>) -> Result<Vec<AgentSnapshot>, CommandError> {
     #[derive(Deserialize)]
     struct AgentsResponse {
         agents: Vec<AgentSnapshot>,
     }
 
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(format!("{API_BASE}/agents"))
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json::<AgentsResponse>()
-        .await
-        .map_err(|e| e.to_string())?;
-
+    let request = {
+        let state = state.lock().unwrap();
+        state.ensure_configured()?;
+        state.request(reqwest::Method::GET, "/agents")
+    };
+    let resp: AgentsResponse = decode(request.send().await?).await?;
     Ok(resp.agents)
 }
 
+#[tracing::instrument(skip(state))]
 #[tauri::command]
-pub async fn submit_task(name: String, description: String) -> Result<SubmitTaskResponse, String> {
-    let client = reqwest::Client::new();
-    client
-        .post(format!("{API_BASE}/tasks"))
-        .json(&SubmitTaskRequest { name, description })
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json::<SubmitTaskResponse>()
-        .await
-        .map_err(|e| e.to_string())
+pub async fn submit_task(
+    state: State<'_, Mutex<AppState>>,
+    name: String,
+    description: String,
+) -> Result<SubmitTaskResponse, CommandError> {
+    let request = {
+        let state = state.lock().unwrap();
+        state.ensure_configured()?;
+        state.request(reqwest::Method::POST, "/tasks")
+    };
+    let response = request.json(&SubmitTaskRequest { name, description }).send().await?;
+    decode(response).await
+}
+
+#[tracing::instrument(skip(state))]
+#[tauri::command]
+pub async fn get_task(state: State<'_, Mutex<AppState>>, task_id: String) -> Result<TaskStatus, CommandError> {
+    let request = {
+        let state = state.lock().unwrap();
+        state.ensure_configured()?;
+        state.request(reqwest::Method::GET, &format!("/tasks/{task_id}"))
+    };
+    decode(request.send().await?).await
+}
+
+#[tracing::instrument(skip(state))]
+#[tauri::command]
+pub async fn cancel_task(state: State<'_, Mutex<AppState>>, task_id: String) -> Result<(), CommandError> {
+    let request = {
+        let state = state.lock().unwrap();
+        state.ensure_configured()?;
+        state.request(reqwest::Method::DELETE, &format!("/tasks/{task_id}"))
+    };
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(status_to_command_error(status, body));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(skip(state))]
+#[tauri::command]
+pub async fn retry_task(
+    state: State<'_, Mutex<AppState>>,
+    task_id: String,
+) -> Result<SubmitTaskResponse, CommandError> {
+    let request = {
+        let state = state.lock().unwrap();
+        state.ensure_configured()?;
+        state.request(reqwest::Method::POST, &format!("/tasks/{task_id}/retry"))
+    };
+    decode(request.send().await?).await
+}
+
+#[tracing::instrument(skip(app, state))]
+#[tauri::command]
+pub fn start_snapshot_stream(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    interval_ms: u64,
+) -> Result<(), CommandError> {
+    if interval_ms == 0 {
+        return Err(CommandError::InvalidArgument("interval_ms must be greater than zero".into()));
+    }
+
+    let (client, base_url, token, cancel) = {
+        let mut state = state.lock().unwrap();
+        if let Some(previous) = state.snapshot_stream_cancel.take() {
+            previous.store(true, Ordering::SeqCst);
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        state.snapshot_stream_cancel = Some(cancel.clone());
+        (state.client.clone(), state.base_url.clone(), state.token.clone(), cancel)
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+        while !cancel.load(Ordering::SeqCst) {
+            interval.tick().await;
+            if cancel.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let request = build_request(&client, &base_url, &token, reqwest::Method::GET, "/snapshot");
+            let result: Result<DashboardSnapshot, CommandError> = async {
+                let response = request.send().await?;
+                decode(response).await
+            }
+            .await;
+
+            match result {
+                Ok(snapshot) => {
+                    let _ = app.emit("dashboard://snapshot", snapshot);
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "snapshot poll failed");
+                    let _ = app.emit("dashboard://error", error);
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+#[tracing::instrument(skip(state))]
+#[tauri::command]
+pub fn stop_snapshot_stream(state: State<'_, Mutex<AppState>>) {
+    if let Some(cancel) = state.lock().unwrap().snapshot_stream_cancel.take() {
+        cancel.store(true, Ordering::SeqCst);
+    }
+}
+
+fn drain_log_lines(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut lines = Vec::new();
+    while let Some(newline) = buffer.iter().position(|&byte| byte == b'\n') {
+        let line = buffer.drain(..=newline).collect::<Vec<_>>();
+        lines.push(String::from_utf8_lossy(&line[..line.len() - 1]).into_owned());
+    }
+    lines
+}
+
+async fn run_agent_log_stream(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    base_url: &str,
+    token: &Option<String>,
+    cancel: &AtomicBool,
+    agent_id: &str,
+) {
+    let request = build_request(
+        client,
+        base_url,
+        token,
+        reqwest::Method::GET,
+        &format!("/agents/{agent_id}/logs"),
+    );
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(error) => {
+            let error = CommandError::from(error);
+            tracing::warn!(%error, %agent_id, "failed to open agent log stream");
+            let _ = app.emit("agent://error", AgentLogError { agent_id: agent_id.to_string(), error });
+            return;
+        }
+    };
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let error = status_to_command_error(status, body);
+        tracing::warn!(%error, %agent_id, "agent log stream returned an error status");
+        let _ = app.emit("agent://error", AgentLogError { agent_id: agent_id.to_string(), error });
+        return;
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = Vec::new();
+    while !cancel.load(Ordering::SeqCst) {
+        let Some(chunk) = stream.next().await else {
+            break;
+        };
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                let error = CommandError::from(error);
+                tracing::warn!(%error, %agent_id, "agent log stream broke mid-read");
+                let _ = app.emit("agent://error", AgentLogError { agent_id: agent_id.to_string(), error });
+                break;
+            }
+        };
+
+        buffer.extend_from_slice(&chunk);
+        for line in drain_log_lines(&mut buffer) {
+            let _ = app.emit("agent://log", AgentLogLine { agent_id: agent_id.to_string(), line });
+        }
+    }
+
+    if !buffer.is_empty() {
+        let line = String::from_utf8_lossy(&buffer).into_owned();
+        let _ = app.emit("agent://log", AgentLogLine { agent_id: agent_id.to_string(), line });
+    }
+}
+
+#[tracing::instrument(skip(app, state))]
+#[tauri::command]
+pub fn stream_agent_logs(app: AppHandle, state: State<'_, Mutex<AppState>>, agent_id: String) {
+    let (client, base_url, token, cancel) = {
+        let mut state = state.lock().unwrap();
+        if let Some(previous) = state.agent_log_cancels.remove(&agent_id) {
+            previous.store(true, Ordering::SeqCst);
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        state.agent_log_cancels.insert(agent_id.clone(), cancel.clone());
+        (state.client.clone(), state.base_url.clone(), state.token.clone(), cancel)
+    };
+
+    tauri::async_runtime::spawn(async move {
+        run_agent_log_stream(&app, &client, &base_url, &token, &cancel, &agent_id).await;
+
+        // Drop our cancellation entry once the stream ends on its own, but
+        // only if a subsequent restart hasn't already replaced it.
+        let mut state = app.state::<Mutex<AppState>>().lock().unwrap();
+        if state.agent_log_cancels.get(&agent_id).is_some_and(|current| Arc::ptr_eq(current, &cancel)) {
+            state.agent_log_cancels.remove(&agent_id);
+        }
+    });
+}
+
+#[tracing::instrument(skip(state))]
+#[tauri::command]
+pub fn stop_agent_logs(state: State<'_, Mutex<AppState>>, agent_id: String) {
+    if let Some(cancel) = state.lock().unwrap().agent_log_cancels.remove(&agent_id) {
+        cancel.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_to_command_error_reports_status_and_body() {
+        let status = reqwest::StatusCode::from_u16(500).unwrap();
+        let error = status_to_command_error(status, "boom".to_string());
+        match error {
+            CommandError::Http { status, body } => {
+                assert_eq!(status, 500);
+                assert_eq!(body, "boom");
+            }
+            other => panic!("expected Http error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn drain_log_lines_splits_on_newlines_and_keeps_partial_tail() {
+        let mut buffer = b"first\nsecond\npartial".to_vec();
+        let lines = drain_log_lines(&mut buffer);
+        assert_eq!(lines, vec!["first".to_string(), "second".to_string()]);
+        assert_eq!(buffer, b"partial");
+    }
+
+    #[test]
+    fn drain_log_lines_handles_a_line_split_across_calls() {
+        let mut buffer = b"hel".to_vec();
+        assert!(drain_log_lines(&mut buffer).is_empty());
+
+        buffer.extend_from_slice(b"lo\n");
+        assert_eq!(drain_log_lines(&mut buffer), vec!["hello".to_string()]);
+        assert!(buffer.is_empty());
+    }
 }
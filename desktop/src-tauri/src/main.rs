@@ -3,16 +3,31 @@
 
 mod commands;
 
+use std::sync::Mutex;
+
+use commands::AppState;
 use tauri::Manager;
 
 fn main() {
+    tracing_subscriber::fmt::init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .manage(Mutex::new(AppState::default()))
         .invoke_handler(tauri::generate_handler![
+            commands::configure,
+            commands::is_configured,
             commands::get_health,
             commands::get_snapshot,
             commands::get_agents,
             commands::submit_task,
+            commands::get_task,
+            commands::cancel_task,
+            commands::retry_task,
+            commands::start_snapshot_stream,
+            commands::stop_snapshot_stream,
+            commands::stream_agent_logs,
+            commands::stop_agent_logs,
         ])
         .setup(|app| {
             #[cfg(debug_assertions)]